@@ -0,0 +1,334 @@
+use super::model;
+use super::Coinbase;
+use crate::shared::Result;
+
+impl Coinbase {
+    pub async fn refresh_market_info(&self) -> Result<()> {
+        // No product metadata is loaded or cached; this returns immediately.
+        Ok(())
+    }
+
+    pub async fn book<T>(&self, market_pair: &str) -> Result<model::Book<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let endpoint = format!("/products/{}/book", market_pair);
+        self.transport
+            .get(&endpoint, Some(&[("level", 2)]))
+            .await
+    }
+
+    pub async fn ticker(&self, market_pair: &str) -> Result<model::Ticker> {
+        let endpoint = format!("/products/{}/ticker", market_pair);
+        self.transport.get(&endpoint, Option::<&()>::None).await
+    }
+
+    pub async fn get_products(&self) -> Result<Vec<model::Product>> {
+        self.transport.get("/products", Option::<&()>::None).await
+    }
+
+    pub async fn stats(&self, market_pair: &str) -> Result<model::ProductStats> {
+        let endpoint = format!("/products/{}/stats", market_pair);
+        self.transport.get(&endpoint, Option::<&()>::None).await
+    }
+
+    pub async fn candles(
+        &self,
+        market_pair: &str,
+        params: Option<&model::CandleRequestParams>,
+    ) -> Result<Vec<model::Candle>> {
+        let endpoint = format!("/products/{}/candles", market_pair);
+        self.transport.get(&endpoint, params).await
+    }
+
+    /// Fetches one page of recent trades for `market_pair` via
+    /// `GET /products/{id}/trades`, following the same before/after
+    /// cursor convention as [`Coinbase::get_fills`].
+    pub async fn trades(
+        &self,
+        market_pair: &str,
+        params: Option<&model::GetTradesReq>,
+    ) -> Result<Vec<model::Trade>> {
+        let endpoint = format!("/products/{}/trades", market_pair);
+        self.transport.get(&endpoint, params).await
+    }
+
+    async fn place_order(&self, req: &model::PlaceOrderRequest) -> Result<model::Order> {
+        self.transport.signed_post("/orders", req).await
+    }
+
+    pub async fn limit_buy(
+        &self,
+        market_pair: &str,
+        size: f64,
+        price: f64,
+    ) -> Result<model::Order> {
+        self.place_order(&model::PlaceOrderRequest {
+            product_id: String::from(market_pair),
+            side: model::OrderSide::Buy,
+            order_type: String::from("limit"),
+            price: Some(price),
+            size: Some(size),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn limit_sell(
+        &self,
+        market_pair: &str,
+        size: f64,
+        price: f64,
+    ) -> Result<model::Order> {
+        self.place_order(&model::PlaceOrderRequest {
+            product_id: String::from(market_pair),
+            side: model::OrderSide::Sell,
+            order_type: String::from("limit"),
+            price: Some(price),
+            size: Some(size),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn market_buy(&self, market_pair: &str, size: f64) -> Result<model::Order> {
+        self.place_order(&model::PlaceOrderRequest {
+            product_id: String::from(market_pair),
+            side: model::OrderSide::Buy,
+            order_type: String::from("market"),
+            size: Some(size),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn market_sell(&self, market_pair: &str, size: f64) -> Result<model::Order> {
+        self.place_order(&model::PlaceOrderRequest {
+            product_id: String::from(market_pair),
+            side: model::OrderSide::Sell,
+            order_type: String::from("market"),
+            size: Some(size),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Coinbase distinguishes a stop-loss sell from a stop-entry buy via
+    /// the `stop` field rather than the order side alone.
+    fn stop_type_for(side: model::OrderSide) -> model::StopType {
+        match side {
+            model::OrderSide::Buy => model::StopType::Entry,
+            model::OrderSide::Sell => model::StopType::Loss,
+        }
+    }
+
+    pub async fn stop_limit_buy(
+        &self,
+        market_pair: &str,
+        size: f64,
+        price: f64,
+        stop_price: f64,
+        time_in_force: Option<model::TimeInForce>,
+    ) -> Result<model::Order> {
+        self.place_order(&model::PlaceOrderRequest {
+            product_id: String::from(market_pair),
+            side: model::OrderSide::Buy,
+            order_type: String::from("limit"),
+            price: Some(price),
+            size: Some(size),
+            time_in_force,
+            stop: Some(model::StopDetails {
+                stop: Self::stop_type_for(model::OrderSide::Buy),
+                stop_price,
+            }),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn stop_limit_sell(
+        &self,
+        market_pair: &str,
+        size: f64,
+        price: f64,
+        stop_price: f64,
+        time_in_force: Option<model::TimeInForce>,
+    ) -> Result<model::Order> {
+        self.place_order(&model::PlaceOrderRequest {
+            product_id: String::from(market_pair),
+            side: model::OrderSide::Sell,
+            order_type: String::from("limit"),
+            price: Some(price),
+            size: Some(size),
+            time_in_force,
+            stop: Some(model::StopDetails {
+                stop: Self::stop_type_for(model::OrderSide::Sell),
+                stop_price,
+            }),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn stop_market_buy(
+        &self,
+        market_pair: &str,
+        size: f64,
+        stop_price: f64,
+    ) -> Result<model::Order> {
+        self.place_order(&model::PlaceOrderRequest {
+            product_id: String::from(market_pair),
+            side: model::OrderSide::Buy,
+            order_type: String::from("market"),
+            size: Some(size),
+            stop: Some(model::StopDetails {
+                stop: Self::stop_type_for(model::OrderSide::Buy),
+                stop_price,
+            }),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn stop_market_sell(
+        &self,
+        market_pair: &str,
+        size: f64,
+        stop_price: f64,
+    ) -> Result<model::Order> {
+        self.place_order(&model::PlaceOrderRequest {
+            product_id: String::from(market_pair),
+            side: model::OrderSide::Sell,
+            order_type: String::from("market"),
+            size: Some(size),
+            stop: Some(model::StopDetails {
+                stop: Self::stop_type_for(model::OrderSide::Sell),
+                stop_price,
+            }),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn cancel_order(
+        &self,
+        id: String,
+        market_pair: Option<&str>,
+    ) -> Result<String> {
+        let endpoint = match market_pair {
+            Some(market_pair) => format!("/orders/{}?product_id={}", id, market_pair),
+            None => format!("/orders/{}", id),
+        };
+        self.transport.signed_delete(&endpoint).await
+    }
+
+    pub async fn cancel_all_orders(&self, market_pair: Option<&str>) -> Result<Vec<String>> {
+        let endpoint = match market_pair {
+            Some(market_pair) => format!("/orders?product_id={}", market_pair),
+            None => String::from("/orders"),
+        };
+        self.transport.signed_delete(&endpoint).await
+    }
+
+    pub async fn get_orders(
+        &self,
+        params: Option<&model::GetOrderRequest>,
+    ) -> Result<Vec<model::Order>> {
+        self.transport.signed_get("/orders", params).await
+    }
+
+    pub async fn get_fills(
+        &self,
+        params: Option<&model::GetFillsReq>,
+    ) -> Result<Vec<model::Fill>> {
+        self.transport.signed_get("/fills", params).await
+    }
+
+    pub async fn get_account(
+        &self,
+        paginator: Option<&model::Paginator>,
+    ) -> Result<Vec<model::Account>> {
+        self.transport.signed_get("/accounts", paginator).await
+    }
+
+    pub async fn get_order(&self, id: String) -> Result<model::Order> {
+        let endpoint = format!("/orders/{}", id);
+        self.transport.signed_get(&endpoint, Option::<&()>::None).await
+    }
+
+    pub async fn get_margin_accounts(&self) -> Result<Vec<model::MarginAccount>> {
+        self.transport
+            .signed_get("/margin/accounts", Option::<&()>::None)
+            .await
+    }
+
+    pub async fn margin_borrow(
+        &self,
+        currency: &str,
+        amount: f64,
+    ) -> Result<model::BorrowResponse> {
+        self.transport
+            .signed_post(
+                "/margin/borrow",
+                &model::BorrowRequest {
+                    currency: String::from(currency),
+                    amount,
+                },
+            )
+            .await
+    }
+
+    pub async fn margin_repay(
+        &self,
+        currency: &str,
+        amount: f64,
+    ) -> Result<model::RepayResponse> {
+        self.transport
+            .signed_post(
+                "/margin/repay",
+                &model::RepayRequest {
+                    currency: String::from(currency),
+                    amount,
+                },
+            )
+            .await
+    }
+
+    pub async fn margin_limit_buy(
+        &self,
+        market_pair: &str,
+        size: f64,
+        price: f64,
+        leverage: f64,
+    ) -> Result<model::Order> {
+        self.place_order(&model::PlaceOrderRequest {
+            product_id: String::from(market_pair),
+            side: model::OrderSide::Buy,
+            order_type: String::from("limit"),
+            price: Some(price),
+            size: Some(size),
+            margin: Some(model::MarginOrderDetails { leverage }),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn margin_limit_sell(
+        &self,
+        market_pair: &str,
+        size: f64,
+        price: f64,
+        leverage: f64,
+    ) -> Result<model::Order> {
+        self.place_order(&model::PlaceOrderRequest {
+            product_id: String::from(market_pair),
+            side: model::OrderSide::Sell,
+            order_type: String::from("limit"),
+            price: Some(price),
+            size: Some(size),
+            margin: Some(model::MarginOrderDetails { leverage }),
+            ..Default::default()
+        })
+        .await
+    }
+}
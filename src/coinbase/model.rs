@@ -0,0 +1,483 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+use crate::errors::OpenLimitError;
+use crate::model::{GetHistoricTradesRequest, Paginator as OpenLimitsPaginator};
+use crate::shared::Result;
+
+/// Coinbase Pro encodes every decimal field (prices, sizes, balances) as a
+/// JSON string (e.g. `"price":"10104.94"`) rather than a JSON number, so
+/// plain `f64`/`Option<f64>` fields need to parse through a string first.
+mod de {
+    use serde::{Deserialize, Deserializer};
+
+    pub fn f64_from_str<'de, D>(deserializer: D) -> std::result::Result<f64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+
+    pub fn f64_from_str_opt<'de, D>(
+        deserializer: D,
+    ) -> std::result::Result<Option<f64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) if !s.is_empty() => s.parse().map(Some).map_err(serde::de::Error::custom),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BookRecordL2 {
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub price: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub size: f64,
+    pub num_orders: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Book<T> {
+    pub sequence: u64,
+    pub bids: Vec<T>,
+    pub asks: Vec<T>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Ticker {
+    pub trade_id: u64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub price: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub size: f64,
+    pub time: DateTime<Utc>,
+}
+
+/// One entry of `GET /products`, used to enumerate market pairs for the
+/// all-tickers snapshot.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Product {
+    pub id: String,
+}
+
+/// `GET /products/{id}/stats`: Coinbase's 24h rolling stats for a pair.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProductStats {
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub open: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub high: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub low: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub volume: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub last: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub volume_30day: f64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Candle {
+    pub time: u64,
+    pub low: f64,
+    pub high: f64,
+    pub open: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct CandleRequestParams {
+    #[serde(flatten)]
+    pub daterange: Option<DateRange>,
+    pub granularity: Option<u32>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DateRange {
+    pub start: Option<NaiveDateTime>,
+    pub end: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct Paginator {
+    pub after: Option<u64>,
+    pub before: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+    Active,
+    Done,
+    Open,
+    Pending,
+    Rejected,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeInForce {
+    Gtc,
+    Gtt,
+    Ioc,
+    Fok,
+}
+
+/// Whether a stop order triggers on the way down (`Loss`, i.e. a stop-loss
+/// sell) or the way up (`Entry`, i.e. a stop-buy breakout), matching
+/// Coinbase's `stop` order field.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum StopType {
+    Loss,
+    Entry,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OrderType {
+    Limit {
+        #[serde(deserialize_with = "de::f64_from_str")]
+        price: f64,
+        #[serde(deserialize_with = "de::f64_from_str")]
+        size: f64,
+        time_in_force: Option<TimeInForce>,
+    },
+    Market {
+        #[serde(deserialize_with = "de::f64_from_str")]
+        size: f64,
+        #[serde(default, deserialize_with = "de::f64_from_str_opt")]
+        funds: Option<f64>,
+    },
+}
+
+/// Coinbase reports a stop order with the same `type: "limit"`/`"market"`
+/// tag as a regular order, plus these two extra top-level fields - so
+/// stop-ness is carried alongside [`OrderType`] rather than as part of it.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub struct StopDetails {
+    pub stop: StopType,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub stop_price: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Order {
+    pub id: String,
+    pub product_id: String,
+    pub side: OrderSide,
+    pub status: OrderStatus,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub _type: OrderType,
+    #[serde(flatten)]
+    pub stop: Option<StopDetails>,
+}
+
+/// Body for `POST /orders`, covering plain and stop variants of both
+/// limit and market orders.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PlaceOrderRequest {
+    pub product_id: String,
+    pub side: OrderSide,
+    #[serde(rename = "type")]
+    pub order_type: String,
+    pub price: Option<f64>,
+    pub size: Option<f64>,
+    pub funds: Option<f64>,
+    pub time_in_force: Option<TimeInForce>,
+    #[serde(flatten)]
+    pub stop: Option<StopDetails>,
+    #[serde(flatten)]
+    pub margin: Option<MarginOrderDetails>,
+}
+
+/// Opts an order into Coinbase's margin profile at the given `leverage`;
+/// Coinbase represents a margin order as a regular order with this field
+/// attached rather than as a distinct order type.
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct MarginOrderDetails {
+    pub leverage: f64,
+}
+
+impl Default for OrderSide {
+    fn default() -> Self {
+        OrderSide::Buy
+    }
+}
+
+/// Request to open a stop-limit order: trigger at `stop_price`, then rest
+/// on the book as a limit order at `price`, mirroring
+/// [`crate::model::OpenLimitOrderRequest`] with the added stop fields.
+#[derive(Debug, Clone)]
+pub struct OpenStopLimitOrderRequest {
+    pub market_pair: String,
+    pub size: f64,
+    pub price: f64,
+    pub stop_price: f64,
+    pub time_in_force: Option<TimeInForce>,
+}
+
+/// Request to open a stop-market order: trigger at `stop_price`, then
+/// execute as a market order, mirroring
+/// [`crate::model::OpenMarketOrderRequest`] with the added stop fields.
+#[derive(Debug, Clone)]
+pub struct OpenStopMarketOrderRequest {
+    pub market_pair: String,
+    pub size: f64,
+    pub stop_price: f64,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct GetOrderRequest {
+    pub status: Option<String>,
+    pub product_id: Option<String>,
+    #[serde(flatten)]
+    pub paginator: Option<Paginator>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Account {
+    pub id: String,
+    pub currency: String,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub balance: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub available: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub hold: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Fill {
+    pub trade_id: u64,
+    pub order_id: String,
+    pub product_id: String,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub price: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub size: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub fee: f64,
+    pub side: String,
+    pub liquidity: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct GetFillsReq {
+    pub order_id: Option<String>,
+    pub product_id: Option<String>,
+    #[serde(flatten)]
+    pub paginator: Option<Paginator>,
+}
+
+/// A single raw trade as returned by `GET /products/{id}/trades`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Trade {
+    pub trade_id: u64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub price: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub size: f64,
+    pub side: String,
+    pub time: DateTime<Utc>,
+}
+
+/// Query params for `GET /products/{id}/trades`, supporting the same
+/// before/after cursor pagination as [`GetFillsReq`].
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct GetTradesReq {
+    #[serde(flatten)]
+    pub paginator: Option<Paginator>,
+}
+
+impl<S> TryFrom<&GetHistoricTradesRequest<S>> for GetTradesReq
+where
+    S: crate::exchange::ExchangeSpec<Pagination = u64>,
+{
+    type Error = OpenLimitError;
+
+    fn try_from(req: &GetHistoricTradesRequest<S>) -> Result<Self> {
+        Ok(Self {
+            paginator: req.paginator.clone().map(paginator_from),
+        })
+    }
+}
+
+fn paginator_from<S>(paginator: OpenLimitsPaginator<S>) -> Paginator
+where
+    S: crate::exchange::ExchangeSpec<Pagination = u64>,
+{
+    Paginator {
+        after: paginator.after,
+        before: paginator.before,
+        limit: paginator.limit,
+    }
+}
+
+/// Request to open a leveraged limit order against the margin book,
+/// mirroring [`crate::model::OpenLimitOrderRequest`] with an added
+/// `leverage` factor.
+#[derive(Debug, Clone)]
+pub struct OpenMarginOrderRequest {
+    pub market_pair: String,
+    pub size: f64,
+    pub price: f64,
+    pub leverage: f64,
+}
+
+/// A margin account balance as returned by `GET /margin/accounts`, the
+/// margin counterpart of [`Account`].
+#[derive(Deserialize, Debug, Clone)]
+pub struct MarginAccount {
+    pub currency: String,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub balance: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub available: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub borrowed: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub interest: f64,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub available_margin: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BorrowRequest {
+    pub currency: String,
+    pub amount: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BorrowResponse {
+    pub currency: String,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub amount: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RepayRequest {
+    pub currency: String,
+    pub amount: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RepayResponse {
+    pub currency: String,
+    #[serde(deserialize_with = "de::f64_from_str")]
+    pub amount: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticker_parses_coinbase_s_quoted_decimals_and_z_suffixed_time() {
+        let ticker: Ticker = serde_json::from_str(
+            r#"{"trade_id":4729,"price":"333.99","size":"0.193","time":"2014-11-07T22:19:28.578544Z"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(ticker.price, 333.99);
+        assert_eq!(ticker.size, 0.193);
+    }
+
+    #[test]
+    fn account_parses_coinbase_s_quoted_decimals() {
+        let account: Account = serde_json::from_str(
+            r#"{"id":"a1","currency":"BTC","balance":"1.100000000000000000","available":"1.00","hold":"0.1"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(account.balance, 1.1);
+        assert_eq!(account.available, 1.0);
+        assert_eq!(account.hold, 0.1);
+    }
+
+    #[test]
+    fn fill_parses_coinbase_s_quoted_decimals_and_z_suffixed_time() {
+        let fill: Fill = serde_json::from_str(
+            r#"{"trade_id":74,"order_id":"o1","product_id":"BTC-USD","price":"10104.94",
+                "size":"0.001","fee":"0.00025","side":"buy","liquidity":"T",
+                "created_at":"2014-11-07T22:19:28.578544Z"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(fill.price, 10104.94);
+        assert_eq!(fill.fee, 0.00025);
+    }
+
+    #[test]
+    fn trade_parses_coinbase_s_quoted_decimals_and_z_suffixed_time() {
+        let trade: Trade = serde_json::from_str(
+            r#"{"trade_id":74,"price":"10104.94","size":"0.001","side":"sell",
+                "time":"2014-11-07T22:19:28.578544Z"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(trade.price, 10104.94);
+        assert_eq!(trade.size, 0.001);
+    }
+
+    #[test]
+    fn order_parses_coinbase_s_quoted_limit_price_and_size() {
+        let order: Order = serde_json::from_str(
+            r#"{"id":"o1","product_id":"BTC-USD","side":"buy","status":"open",
+                "created_at":"2014-11-07T22:19:28.578544Z","type":"limit",
+                "price":"10104.94","size":"0.001","time_in_force":"gtc"}"#,
+        )
+        .unwrap();
+
+        match order._type {
+            OrderType::Limit { price, size, .. } => {
+                assert_eq!(price, 10104.94);
+                assert_eq!(size, 0.001);
+            }
+            OrderType::Market { .. } => panic!("expected a limit order"),
+        }
+    }
+
+    #[test]
+    fn margin_account_parses_coinbase_s_quoted_decimals() {
+        let account: MarginAccount = serde_json::from_str(
+            r#"{"currency":"BTC","balance":"1.1","available":"1.0",
+                "borrowed":"0.5","interest":"0.001","available_margin":"2.2"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(account.balance, 1.1);
+        assert_eq!(account.borrowed, 0.5);
+        assert_eq!(account.available_margin, 2.2);
+    }
+
+    #[test]
+    fn product_stats_parses_coinbase_s_quoted_decimals() {
+        let stats: ProductStats = serde_json::from_str(
+            r#"{"open":"6745.61","high":"7292.11","low":"6650.00",
+                "volume":"26185.47034103","last":"6813.19","volume_30day":"1135127.30273463"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(stats.open, 6745.61);
+        assert_eq!(stats.last, 6813.19);
+    }
+}
@@ -0,0 +1,358 @@
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use futures::stream::Stream;
+use serde::{Deserialize, Serialize};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::errors::OpenLimitError;
+use crate::exchange::Exchange;
+use crate::model::AskBid;
+use crate::shared::Result;
+
+use super::model::OrderSide;
+use super::transport::WebsocketAuth;
+use super::Coinbase;
+
+const SANDBOX_FEED_URL: &str = "wss://ws-feed-public.sandbox.pro.coinbase.com";
+const PROD_FEED_URL: &str = "wss://ws-feed.pro.coinbase.com";
+
+/// A live update pushed over Coinbase's `ws-feed`, decoded into the same
+/// shapes `ExchangeMarketData`/`ExchangeAccount` use for REST responses so
+/// callers can maintain a local order book without polling.
+#[derive(Debug, Clone)]
+pub enum StreamMessage {
+    Level2Update {
+        market_pair: String,
+        bids: Vec<AskBid>,
+        asks: Vec<AskBid>,
+    },
+    Ticker {
+        market_pair: String,
+        price: f64,
+    },
+    OrderUpdate {
+        market_pair: String,
+        order_id: String,
+        side: OrderSide,
+        status: String,
+    },
+}
+
+/// Subscribes to Coinbase's `ws-feed` channels and yields decoded
+/// [`StreamMessage`]s, so a caller can maintain a local order book or order
+/// status without polling the REST endpoints.
+#[async_trait]
+pub trait ExchangeStream {
+    async fn subscribe_order_book(
+        &self,
+        market_pair: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamMessage>> + Send>>>;
+
+    async fn subscribe_ticker(
+        &self,
+        market_pair: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamMessage>> + Send>>>;
+
+    async fn subscribe_user_orders(
+        &self,
+        market_pair: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamMessage>> + Send>>>;
+}
+
+#[derive(Serialize)]
+struct SubscribeMessage<'a> {
+    #[serde(rename = "type")]
+    _type: &'a str,
+    product_ids: Vec<&'a str>,
+    channels: Vec<&'a str>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    auth: Option<WebsocketAuth>,
+}
+
+impl Serialize for WebsocketAuth {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(4))?;
+        map.serialize_entry("key", &self.key)?;
+        map.serialize_entry("passphrase", &self.passphrase)?;
+        map.serialize_entry("signature", &self.signature)?;
+        map.serialize_entry("timestamp", &self.timestamp)?;
+        map.end()
+    }
+}
+
+/// Coinbase's `ws-feed` encodes every price/size as a JSON string (e.g.
+/// `["buy","10101.80","0.162567"]`), the same as its REST responses, so
+/// these are parsed as strings here and converted to `f64` in
+/// [`Coinbase::decode_feed_message`] rather than deserialized directly.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum FeedMessage {
+    Snapshot {
+        product_id: String,
+        bids: Vec<(String, String)>,
+        asks: Vec<(String, String)>,
+    },
+    L2update {
+        product_id: String,
+        changes: Vec<(String, String, String)>,
+    },
+    Ticker {
+        product_id: String,
+        price: String,
+    },
+    Received {
+        product_id: String,
+        order_id: String,
+        side: OrderSide,
+    },
+    Done {
+        product_id: String,
+        order_id: String,
+        side: OrderSide,
+        reason: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[async_trait]
+impl ExchangeStream for Exchange<Coinbase> {
+    async fn subscribe_order_book(
+        &self,
+        market_pair: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamMessage>> + Send>>> {
+        self.inner.subscribe(market_pair, "level2", false).await
+    }
+
+    async fn subscribe_ticker(
+        &self,
+        market_pair: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamMessage>> + Send>>> {
+        self.inner.subscribe(market_pair, "ticker", false).await
+    }
+
+    async fn subscribe_user_orders(
+        &self,
+        market_pair: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamMessage>> + Send>>> {
+        self.inner.subscribe(market_pair, "user", true).await
+    }
+}
+
+impl Coinbase {
+    async fn subscribe(
+        &self,
+        market_pair: &str,
+        channel: &str,
+        authenticated: bool,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamMessage>> + Send>>> {
+        let feed_url = if self.sandbox {
+            SANDBOX_FEED_URL
+        } else {
+            PROD_FEED_URL
+        };
+
+        let auth = if authenticated {
+            Some(self.transport.websocket_auth()?)
+        } else {
+            None
+        };
+
+        let (socket, _) = tokio_tungstenite::connect_async(feed_url)
+            .await
+            .map_err(|e| OpenLimitError::Other(e.to_string()))?;
+        let (mut sink, stream) = socket.split();
+
+        let subscribe = SubscribeMessage {
+            _type: "subscribe",
+            product_ids: vec![market_pair],
+            channels: vec![channel],
+            auth,
+        };
+        let payload = serde_json::to_string(&subscribe)
+            .map_err(|e| OpenLimitError::Other(e.to_string()))?;
+        sink.send(Message::Text(payload))
+            .await
+            .map_err(|e| OpenLimitError::Other(e.to_string()))?;
+
+        // The feed doesn't require further client frames once subscribed, so
+        // the write half is dropped when this function returns; the read
+        // half alone keeps the connection open.
+        let _sink = sink;
+
+        let messages = stream.filter_map(|frame| async move {
+            let frame = match frame {
+                Ok(frame) => frame,
+                Err(e) => return Some(Err(OpenLimitError::Other(e.to_string()))),
+            };
+            let text = match frame {
+                Message::Text(text) => text,
+                _ => return None,
+            };
+            match serde_json::from_str::<FeedMessage>(&text) {
+                Ok(message) => Self::decode_feed_message(message),
+                Err(_) => None,
+            }
+        });
+
+        Ok(Box::pin(messages))
+    }
+
+    fn decode_feed_message(message: FeedMessage) -> Option<Result<StreamMessage>> {
+        Some(Self::try_decode_feed_message(message))
+    }
+
+    fn try_decode_feed_message(message: FeedMessage) -> Result<StreamMessage> {
+        match message {
+            FeedMessage::Snapshot {
+                product_id,
+                bids,
+                asks,
+            } => Ok(StreamMessage::Level2Update {
+                market_pair: product_id,
+                bids: parse_levels(bids)?,
+                asks: parse_levels(asks)?,
+            }),
+            FeedMessage::L2update {
+                product_id,
+                changes,
+            } => {
+                let mut bids = Vec::new();
+                let mut asks = Vec::new();
+                for (side, price, qty) in changes {
+                    let entry = AskBid {
+                        price: parse_decimal(&price)?,
+                        qty: parse_decimal(&qty)?,
+                    };
+                    if side == "buy" {
+                        bids.push(entry);
+                    } else {
+                        asks.push(entry);
+                    }
+                }
+                Ok(StreamMessage::Level2Update {
+                    market_pair: product_id,
+                    bids,
+                    asks,
+                })
+            }
+            FeedMessage::Ticker { product_id, price } => Ok(StreamMessage::Ticker {
+                market_pair: product_id,
+                price: parse_decimal(&price)?,
+            }),
+            FeedMessage::Received {
+                product_id,
+                order_id,
+                side,
+            } => Ok(StreamMessage::OrderUpdate {
+                market_pair: product_id,
+                order_id,
+                side,
+                status: String::from("received"),
+            }),
+            FeedMessage::Done {
+                product_id,
+                order_id,
+                side,
+                reason,
+            } => Ok(StreamMessage::OrderUpdate {
+                market_pair: product_id,
+                order_id,
+                side,
+                status: reason,
+            }),
+            FeedMessage::Error { message } => Err(OpenLimitError::Other(message)),
+        }
+    }
+}
+
+/// Coinbase's feed encodes price/size as quoted decimals, same as its REST
+/// responses; parse one into the `f64` [`StreamMessage`]/[`AskBid`] use.
+fn parse_decimal(value: &str) -> Result<f64> {
+    value
+        .parse()
+        .map_err(|e| OpenLimitError::Other(format!("invalid Coinbase decimal {:?}: {}", value, e)))
+}
+
+fn parse_levels(levels: Vec<(String, String)>) -> Result<Vec<AskBid>> {
+    levels
+        .into_iter()
+        .map(|(price, qty)| {
+            Ok(AskBid {
+                price: parse_decimal(&price)?,
+                qty: parse_decimal(&qty)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_a_realistic_snapshot_message() {
+        let message: FeedMessage = serde_json::from_str(
+            r#"{"type":"snapshot","product_id":"BTC-USD",
+                "bids":[["10101.10","0.45054140"]],
+                "asks":[["10102.55","0.57753524"]]}"#,
+        )
+        .unwrap();
+
+        match Coinbase::try_decode_feed_message(message).unwrap() {
+            StreamMessage::Level2Update {
+                market_pair,
+                bids,
+                asks,
+            } => {
+                assert_eq!(market_pair, "BTC-USD");
+                assert_eq!(bids.len(), 1);
+                assert_eq!(bids[0].price, 10101.10);
+                assert_eq!(bids[0].qty, 0.45054140);
+                assert_eq!(asks.len(), 1);
+                assert_eq!(asks[0].price, 10102.55);
+                assert_eq!(asks[0].qty, 0.57753524);
+            }
+            other => panic!("expected a Level2Update, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decodes_a_realistic_l2update_message() {
+        let message: FeedMessage = serde_json::from_str(
+            r#"{"type":"l2update","product_id":"BTC-USD",
+                "changes":[["buy","10101.80","0.162567"]]}"#,
+        )
+        .unwrap();
+
+        match Coinbase::try_decode_feed_message(message).unwrap() {
+            StreamMessage::Level2Update { bids, asks, .. } => {
+                assert_eq!(bids.len(), 1);
+                assert_eq!(bids[0].price, 10101.80);
+                assert_eq!(bids[0].qty, 0.162567);
+                assert!(asks.is_empty());
+            }
+            other => panic!("expected a Level2Update, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decodes_a_realistic_ticker_message() {
+        let message: FeedMessage = serde_json::from_str(
+            r#"{"type":"ticker","product_id":"BTC-USD","price":"10104.94"}"#,
+        )
+        .unwrap();
+
+        match Coinbase::try_decode_feed_message(message).unwrap() {
+            StreamMessage::Ticker { price, .. } => assert_eq!(price, 10104.94),
+            other => panic!("expected a Ticker, got {:?}", other),
+        }
+    }
+}